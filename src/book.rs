@@ -8,15 +8,23 @@
  *   5. A contact that was favorited can be unfavorited.
  *   6. A contact can be removed from the contact book by its unique id, which
  *      removes all references to the contact within the contact book.
+ *   7. Two contacts can connect by sending, accepting, or declining a contact
+ *      request rather than being linked immediately.
+ *   8. The whole contact book can be exported to a versioned snapshot string
+ *      and imported back, preserving every contact's unique id.
  */
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::Display;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::contact::Contact;
+use crate::query::{self, QueryError};
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+const CURRENT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct ContactBookEntryId(Uuid);
 
 impl ContactBookEntryId {
@@ -25,7 +33,7 @@ impl ContactBookEntryId {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 struct ContactBookEntries(HashMap<ContactBookEntryId, Contact>);
 
 impl ContactBookEntries {
@@ -53,7 +61,7 @@ impl ContactBookEntries {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 struct FavoriteContacts(HashSet<ContactBookEntryId>);
 
 impl FavoriteContacts {
@@ -69,17 +77,179 @@ impl FavoriteContacts {
         self.0.remove(contact_id)
     }
 
+    fn contains(&self, contact_id: &ContactBookEntryId) -> bool {
+        self.0.contains(contact_id)
+    }
+
     fn as_vector(&self) -> Vec<ContactBookEntryId> {
         self.0.iter().cloned().collect()
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+struct ContactLists(HashMap<String, HashSet<ContactBookEntryId>>);
+
+impl ContactLists {
+    fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    fn create(&mut self, name: &str) -> Result<(), ContactBookError> {
+        if self.0.contains_key(name) {
+            return Err(ContactBookError::ListAlreadyExists);
+        }
+
+        self.0.insert(name.to_owned(), HashSet::new());
+        Ok(())
+    }
+
+    fn delete(&mut self, name: &str) -> Result<(), ContactBookError> {
+        match self.0.remove(name) {
+            Some(_) => Ok(()),
+            None => Err(ContactBookError::NoSuchList),
+        }
+    }
+
+    fn add_contact(
+        &mut self,
+        name: &str,
+        contact_id: &ContactBookEntryId,
+    ) -> Result<(), ContactBookError> {
+        match self.0.get_mut(name) {
+            Some(members) => {
+                members.insert(contact_id.clone());
+                Ok(())
+            }
+            None => Err(ContactBookError::NoSuchList),
+        }
+    }
+
+    fn remove_contact(
+        &mut self,
+        name: &str,
+        contact_id: &ContactBookEntryId,
+    ) -> Result<(), ContactBookError> {
+        match self.0.get_mut(name) {
+            Some(members) => {
+                if members.remove(contact_id) {
+                    Ok(())
+                } else {
+                    Err(ContactBookError::ContactWasNotInList)
+                }
+            }
+            None => Err(ContactBookError::NoSuchList),
+        }
+    }
+
+    fn members(&self, name: &str) -> Result<&HashSet<ContactBookEntryId>, ContactBookError> {
+        self.0.get(name).ok_or(ContactBookError::NoSuchList)
+    }
+
+    fn remove_contact_from_all(&mut self, contact_id: &ContactBookEntryId) {
+        for members in self.0.values_mut() {
+            members.remove(contact_id);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ContactRequestStatus {
+    None,
+    RequestSent,
+    RequestReceived,
+    Accepted,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+struct ContactRequests {
+    pending: HashSet<(ContactBookEntryId, ContactBookEntryId)>,
+    connections: HashSet<(ContactBookEntryId, ContactBookEntryId)>,
+}
+
+impl ContactRequests {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn send(&mut self, from: &ContactBookEntryId, to: &ContactBookEntryId) {
+        self.pending.insert((from.clone(), to.clone()));
+    }
+
+    fn accept(
+        &mut self,
+        from: &ContactBookEntryId,
+        to: &ContactBookEntryId,
+    ) -> Result<(), ContactBookError> {
+        if !self.pending.remove(&(from.clone(), to.clone())) {
+            return Err(ContactBookError::NoSuchContactRequest);
+        }
+
+        self.connections.insert((from.clone(), to.clone()));
+        self.connections.insert((to.clone(), from.clone()));
+        Ok(())
+    }
+
+    fn decline(
+        &mut self,
+        from: &ContactBookEntryId,
+        to: &ContactBookEntryId,
+    ) -> Result<(), ContactBookError> {
+        if self.pending.remove(&(from.clone(), to.clone())) {
+            Ok(())
+        } else {
+            Err(ContactBookError::NoSuchContactRequest)
+        }
+    }
+
+    fn status(&self, from: &ContactBookEntryId, to: &ContactBookEntryId) -> ContactRequestStatus {
+        if self.connections.contains(&(from.clone(), to.clone())) {
+            ContactRequestStatus::Accepted
+        } else if self.pending.contains(&(from.clone(), to.clone())) {
+            ContactRequestStatus::RequestSent
+        } else if self.pending.contains(&(to.clone(), from.clone())) {
+            ContactRequestStatus::RequestReceived
+        } else {
+            ContactRequestStatus::None
+        }
+    }
+
+    fn incoming(&self, contact_id: &ContactBookEntryId) -> Vec<ContactBookEntryId> {
+        self.pending
+            .iter()
+            .filter(|(_, to)| to == contact_id)
+            .map(|(from, _)| from.clone())
+            .collect()
+    }
+
+    fn outgoing(&self, contact_id: &ContactBookEntryId) -> Vec<ContactBookEntryId> {
+        self.pending
+            .iter()
+            .filter(|(from, _)| from == contact_id)
+            .map(|(_, to)| to.clone())
+            .collect()
+    }
+
+    fn remove_contact_from_all(&mut self, contact_id: &ContactBookEntryId) {
+        self.pending
+            .retain(|(from, to)| from != contact_id && to != contact_id);
+        self.connections
+            .retain(|(from, to)| from != contact_id && to != contact_id);
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ContactBookError {
     NoSuchContactInBook,
     CannotFavoriteNonexistantContact,
     ContactWasNotAFavorite,
     CannotRemoveNonexistantContact,
+    ListAlreadyExists,
+    NoSuchList,
+    CannotAddNonexistantContactToList,
+    ContactWasNotInList,
+    NoSuchContactRequest,
+    MalformedSnapshot,
+    UnsupportedFormatVersion(u32),
 }
 
 impl Error for ContactBookError {}
@@ -96,14 +266,35 @@ impl Display for ContactBookError {
             Self::CannotRemoveNonexistantContact => {
                 write!(f, "Cannot remove a nonexistant contact.")
             }
+            Self::ListAlreadyExists => write!(f, "A list with that name already exists."),
+            Self::NoSuchList => write!(f, "There is no list with that name."),
+            Self::CannotAddNonexistantContactToList => {
+                write!(f, "Cannot add a nonexistant contact to a list.")
+            }
+            Self::ContactWasNotInList => write!(f, "The given contact was not in that list."),
+            Self::NoSuchContactRequest => {
+                write!(f, "There is no such contact request between those contacts.")
+            }
+            Self::MalformedSnapshot => write!(f, "The given snapshot could not be parsed."),
+            Self::UnsupportedFormatVersion(version) => {
+                write!(f, "Unsupported contact book format version {version}.")
+            }
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize)]
+struct ContactBookSnapshot {
+    format_version: u32,
+    book: ContactBook,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ContactBook {
     contacts: ContactBookEntries,
     favorites: FavoriteContacts,
+    lists: ContactLists,
+    requests: ContactRequests,
 }
 
 impl ContactBook {
@@ -111,6 +302,8 @@ impl ContactBook {
         ContactBook {
             contacts: ContactBookEntries::new(),
             favorites: FavoriteContacts::new(),
+            lists: ContactLists::new(),
+            requests: ContactRequests::new(),
         }
     }
 
@@ -174,11 +367,170 @@ impl ContactBook {
         contact_id: ContactBookEntryId,
     ) -> Result<Self, ContactBookError> {
         self.favorites.remove(&contact_id);
+        self.lists.remove_contact_from_all(&contact_id);
+        self.requests.remove_contact_from_all(&contact_id);
         match self.contacts.remove(&contact_id) {
             Some(_) => Ok(self),
             None => Err(ContactBookError::CannotRemoveNonexistantContact),
         }
     }
+
+    pub fn create_list(mut self, name: &str) -> Result<Self, ContactBookError> {
+        self.lists.create(name)?;
+        Ok(self)
+    }
+
+    pub fn delete_list(mut self, name: &str) -> Result<Self, ContactBookError> {
+        self.lists.delete(name)?;
+        Ok(self)
+    }
+
+    pub fn add_contact_to_list(
+        mut self,
+        name: &str,
+        contact_id: &ContactBookEntryId,
+    ) -> Result<Self, ContactBookError> {
+        if self.contacts.get(contact_id).is_none() {
+            return Err(ContactBookError::CannotAddNonexistantContactToList);
+        }
+
+        self.lists.add_contact(name, contact_id)?;
+        Ok(self)
+    }
+
+    pub fn remove_contact_from_list(
+        mut self,
+        name: &str,
+        contact_id: &ContactBookEntryId,
+    ) -> Result<Self, ContactBookError> {
+        self.lists.remove_contact(name, contact_id)?;
+        Ok(self)
+    }
+
+    pub fn list_contacts_in_list(
+        &self,
+        name: &str,
+    ) -> Result<Vec<(&ContactBookEntryId, &Contact)>, ContactBookError> {
+        let members = self.lists.members(name)?;
+
+        Ok(self
+            .contacts
+            .as_vector()
+            .into_iter()
+            .filter(|(id, _)| members.contains(id))
+            .collect())
+    }
+
+    pub fn send_request(
+        mut self,
+        from: &ContactBookEntryId,
+        to: &ContactBookEntryId,
+    ) -> Result<Self, ContactBookError> {
+        self.contacts
+            .get(from)
+            .ok_or(ContactBookError::NoSuchContactInBook)?;
+        self.contacts
+            .get(to)
+            .ok_or(ContactBookError::NoSuchContactInBook)?;
+
+        self.requests.send(from, to);
+        Ok(self)
+    }
+
+    pub fn accept_request(
+        mut self,
+        from: &ContactBookEntryId,
+        to: &ContactBookEntryId,
+    ) -> Result<Self, ContactBookError> {
+        self.requests.accept(from, to)?;
+        Ok(self)
+    }
+
+    pub fn decline_request(
+        mut self,
+        from: &ContactBookEntryId,
+        to: &ContactBookEntryId,
+    ) -> Result<Self, ContactBookError> {
+        self.requests.decline(from, to)?;
+        Ok(self)
+    }
+
+    pub fn incoming_requests(&self, contact_id: &ContactBookEntryId) -> Vec<ContactBookEntryId> {
+        self.requests.incoming(contact_id)
+    }
+
+    pub fn outgoing_requests(&self, contact_id: &ContactBookEntryId) -> Vec<ContactBookEntryId> {
+        self.requests.outgoing(contact_id)
+    }
+
+    pub fn request_status(
+        &self,
+        from: &ContactBookEntryId,
+        to: &ContactBookEntryId,
+    ) -> ContactRequestStatus {
+        self.requests.status(from, to)
+    }
+
+    pub fn query(&self, input: &str) -> Result<Vec<(&ContactBookEntryId, &Contact)>, QueryError> {
+        let predicate = query::parse(input)?;
+
+        Ok(self
+            .contacts
+            .as_vector()
+            .into_iter()
+            .filter(|(id, contact)| predicate.eval(contact, self.favorites.contains(id)))
+            .collect())
+    }
+
+    pub fn export(&self) -> String {
+        let snapshot = ContactBookSnapshot {
+            format_version: CURRENT_FORMAT_VERSION,
+            book: self.clone(),
+        };
+
+        serde_json::to_string(&snapshot).expect("a ContactBook always serializes")
+    }
+
+    pub fn import(data: &str) -> Result<Self, ContactBookError> {
+        let snapshot: ContactBookSnapshot =
+            serde_json::from_str(data).map_err(|_| ContactBookError::MalformedSnapshot)?;
+
+        if snapshot.format_version != CURRENT_FORMAT_VERSION {
+            return Err(ContactBookError::UnsupportedFormatVersion(
+                snapshot.format_version,
+            ));
+        }
+
+        snapshot.book.check_referenced_ids_exist()?;
+        Ok(snapshot.book)
+    }
+
+    fn check_referenced_ids_exist(&self) -> Result<(), ContactBookError> {
+        let exists = |id: &ContactBookEntryId| {
+            if self.contacts.get(id).is_some() {
+                Ok(())
+            } else {
+                Err(ContactBookError::NoSuchContactInBook)
+            }
+        };
+
+        for id in self.favorites.as_vector().iter() {
+            exists(id)?;
+        }
+
+        for members in self.lists.0.values() {
+            for id in members {
+                exists(id)?;
+            }
+        }
+
+        for (from, to) in self.requests.pending.iter().chain(&self.requests.connections) {
+            exists(from)?;
+            exists(to)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -261,4 +613,242 @@ mod tests {
 
         assert_eq!(contacts.get_favorite_contact_ids(), Vec::new())
     }
+
+    #[test]
+    fn querying_filters_contacts_by_the_query_language() {
+        let (foo_id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let (_, contacts) = contacts.add_contact(Contact::new("Baz Qux").unwrap());
+        let contacts = contacts.add_favorite_contact(&foo_id).unwrap();
+
+        assert_eq!(
+            contacts.query("foo").unwrap(),
+            vec![(&foo_id, contacts.get_contact(&foo_id).unwrap())]
+        );
+
+        assert_eq!(contacts.query("is:favorite").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn querying_with_an_unknown_field_returns_a_query_error() {
+        let contacts = ContactBook::new();
+
+        assert_eq!(
+            contacts.query("nickname:foo"),
+            Err(QueryError::UnknownField("nickname".to_owned()))
+        );
+    }
+
+    #[test]
+    fn creating_a_list_that_already_exists_returns_an_error() {
+        let contacts = ContactBook::new().create_list("Work").unwrap();
+
+        let contacts = contacts.create_list("Work");
+
+        assert_eq!(contacts, Err(ContactBookError::ListAlreadyExists));
+    }
+
+    #[test]
+    fn adding_a_contact_to_a_nonexistant_list_returns_an_error() {
+        let (id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+
+        let contacts = contacts.add_contact_to_list("Work", &id);
+
+        assert_eq!(contacts, Err(ContactBookError::NoSuchList));
+    }
+
+    #[test]
+    fn adding_a_nonexistant_contact_to_a_list_returns_an_error() {
+        let id = ContactBookEntryId::new();
+        let contacts = ContactBook::new().create_list("Work").unwrap();
+
+        let contacts = contacts.add_contact_to_list("Work", &id);
+
+        assert_eq!(
+            contacts,
+            Err(ContactBookError::CannotAddNonexistantContactToList)
+        );
+    }
+
+    #[test]
+    fn listing_contacts_in_a_list_only_returns_its_members() {
+        let (foo_id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let (_, contacts) = contacts.add_contact(Contact::new("Baz Qux").unwrap());
+        let contacts = contacts.create_list("Work").unwrap();
+        let contacts = contacts.add_contact_to_list("Work", &foo_id).unwrap();
+
+        assert_eq!(
+            contacts.list_contacts_in_list("Work").unwrap(),
+            vec![(&foo_id, contacts.get_contact(&foo_id).unwrap())]
+        );
+    }
+
+    #[test]
+    fn removing_a_contact_not_in_a_list_returns_an_error() {
+        let (id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let contacts = contacts.create_list("Work").unwrap();
+
+        let contacts = contacts.remove_contact_from_list("Work", &id);
+
+        assert_eq!(contacts, Err(ContactBookError::ContactWasNotInList));
+    }
+
+    #[test]
+    fn deleting_a_nonexistant_list_returns_an_error() {
+        let contacts = ContactBook::new();
+
+        let contacts = contacts.delete_list("Work");
+
+        assert_eq!(contacts, Err(ContactBookError::NoSuchList));
+    }
+
+    #[test]
+    fn removing_a_contact_from_the_book_removes_it_from_every_list() {
+        let (id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let contacts = contacts.create_list("Work").unwrap();
+        let contacts = contacts.add_contact_to_list("Work", &id).unwrap();
+
+        let contacts = contacts.remove_contact(id).unwrap();
+
+        assert_eq!(contacts.list_contacts_in_list("Work").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn sending_a_request_referencing_a_missing_contact_returns_an_error() {
+        let (id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let missing_id = ContactBookEntryId::new();
+
+        let contacts = contacts.send_request(&id, &missing_id);
+
+        assert_eq!(contacts, Err(ContactBookError::NoSuchContactInBook));
+    }
+
+    #[test]
+    fn accepting_a_request_that_was_never_sent_returns_an_error() {
+        let (from_id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let (to_id, contacts) = contacts.add_contact(Contact::new("Baz Qux").unwrap());
+
+        let contacts = contacts.accept_request(&from_id, &to_id);
+
+        assert_eq!(contacts, Err(ContactBookError::NoSuchContactRequest));
+    }
+
+    #[test]
+    fn sending_and_accepting_a_request_moves_the_pair_into_connections() {
+        let (from_id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let (to_id, contacts) = contacts.add_contact(Contact::new("Baz Qux").unwrap());
+
+        let contacts = contacts.send_request(&from_id, &to_id).unwrap();
+
+        assert_eq!(
+            contacts.request_status(&from_id, &to_id),
+            ContactRequestStatus::RequestSent
+        );
+        assert_eq!(
+            contacts.request_status(&to_id, &from_id),
+            ContactRequestStatus::RequestReceived
+        );
+        assert_eq!(contacts.outgoing_requests(&from_id), vec![to_id.clone()]);
+        assert_eq!(contacts.incoming_requests(&to_id), vec![from_id.clone()]);
+
+        let contacts = contacts.accept_request(&from_id, &to_id).unwrap();
+
+        assert_eq!(
+            contacts.request_status(&from_id, &to_id),
+            ContactRequestStatus::Accepted
+        );
+        assert_eq!(
+            contacts.request_status(&to_id, &from_id),
+            ContactRequestStatus::Accepted
+        );
+        assert_eq!(contacts.outgoing_requests(&from_id), Vec::new());
+    }
+
+    #[test]
+    fn declining_a_request_clears_it_without_creating_a_connection() {
+        let (from_id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let (to_id, contacts) = contacts.add_contact(Contact::new("Baz Qux").unwrap());
+
+        let contacts = contacts.send_request(&from_id, &to_id).unwrap();
+        let contacts = contacts.decline_request(&from_id, &to_id).unwrap();
+
+        assert_eq!(
+            contacts.request_status(&from_id, &to_id),
+            ContactRequestStatus::None
+        );
+    }
+
+    #[test]
+    fn removing_a_contact_purges_its_pending_and_mutual_requests() {
+        let (from_id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let (to_id, contacts) = contacts.add_contact(Contact::new("Baz Qux").unwrap());
+
+        let contacts = contacts
+            .send_request(&from_id, &to_id)
+            .unwrap()
+            .accept_request(&from_id, &to_id)
+            .unwrap();
+
+        let contacts = contacts.remove_contact(from_id.clone()).unwrap();
+
+        assert_eq!(
+            contacts.request_status(&from_id, &to_id),
+            ContactRequestStatus::None
+        );
+    }
+
+    #[test]
+    fn exporting_and_importing_a_book_round_trips_its_contacts_and_ids() {
+        let (id, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let contacts = contacts.add_favorite_contact(&id).unwrap();
+
+        let imported = ContactBook::import(&contacts.export()).unwrap();
+
+        assert_eq!(imported, contacts);
+        assert_eq!(imported.get_favorite_contact_ids(), vec![id]);
+    }
+
+    #[test]
+    fn importing_an_unsupported_format_version_returns_an_error() {
+        let contacts = ContactBook::new();
+        let data = contacts.export().replace("\"format_version\":1", "\"format_version\":9999");
+
+        assert_eq!(
+            ContactBook::import(&data),
+            Err(ContactBookError::UnsupportedFormatVersion(9999))
+        );
+    }
+
+    #[test]
+    fn importing_malformed_data_returns_an_error() {
+        assert_eq!(
+            ContactBook::import("not json"),
+            Err(ContactBookError::MalformedSnapshot)
+        );
+    }
+
+    #[test]
+    fn importing_a_contact_with_an_invalid_email_returns_an_error() {
+        let (_, contacts) = ContactBook::new().add_contact(Contact::new("Foo Bar").unwrap());
+        let exported = contacts.export();
+        let corrupted = exported.replacen("\"email\":null", "\"email\":\"not-an-email\"", 1);
+
+        assert_ne!(exported, corrupted, "the field we meant to corrupt is present");
+
+        assert_eq!(
+            ContactBook::import(&corrupted),
+            Err(ContactBookError::MalformedSnapshot)
+        );
+    }
+
+    #[test]
+    fn a_snapshot_referencing_a_missing_contact_fails_validation() {
+        let missing_id = ContactBookEntryId::new();
+        let mut contacts = ContactBook::new();
+        contacts.favorites.insert(missing_id);
+
+        assert_eq!(
+            contacts.check_referenced_ids_exist(),
+            Err(ContactBookError::NoSuchContactInBook)
+        );
+    }
 }
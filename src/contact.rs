@@ -9,17 +9,25 @@
  */
 use std::{collections::HashMap, error::Error, fmt::Display};
 
-#[derive(Debug)]
-pub struct ContactError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq)]
+pub enum ContactError {
+    InvalidEmail,
+    InvalidPhoneNumber,
+}
 
 impl Error for ContactError {}
 impl Display for ContactError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::InvalidEmail => write!(f, "The given email address is not valid."),
+            Self::InvalidPhoneNumber => write!(f, "The given phone number is not valid."),
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Default)]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
 struct ContactName(String);
 
 impl ContactName {
@@ -32,7 +40,7 @@ impl ContactName {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 struct ContactAddress(String);
 
 impl ContactAddress {
@@ -45,12 +53,46 @@ impl ContactAddress {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String")]
 struct ContactEmail(String);
 
+impl TryFrom<String> for ContactEmail {
+    type Error = ContactError;
+
+    fn try_from(email: String) -> Result<Self, Self::Error> {
+        Self::new(&email)
+    }
+}
+
 impl ContactEmail {
     fn new(email: &str) -> Result<Self, ContactError> {
-        Ok(Self(email.to_owned()))
+        if Self::is_valid(email) {
+            Ok(Self(email.to_owned()))
+        } else {
+            Err(ContactError::InvalidEmail)
+        }
+    }
+
+    fn is_valid(email: &str) -> bool {
+        if email.matches('@').count() != 1 {
+            return false;
+        }
+
+        let Some((local, domain)) = email.split_once('@') else {
+            return false;
+        };
+
+        if local.is_empty() {
+            return false;
+        }
+
+        match domain.rsplit_once('.') {
+            Some((domain_name, top_level_domain)) => {
+                !domain_name.is_empty() && !top_level_domain.is_empty()
+            }
+            None => false,
+        }
     }
 
     fn get_email(&self) -> &str {
@@ -58,12 +100,41 @@ impl ContactEmail {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String")]
 struct ContactPhoneNumber(String);
 
+impl TryFrom<String> for ContactPhoneNumber {
+    type Error = ContactError;
+
+    fn try_from(phone_number: String) -> Result<Self, Self::Error> {
+        Self::new(&phone_number)
+    }
+}
+
 impl ContactPhoneNumber {
     fn new(phone_number: &str) -> Result<Self, ContactError> {
-        Ok(Self(phone_number.to_owned()))
+        let normalized = Self::normalize(phone_number);
+
+        if Self::is_valid(&normalized) {
+            Ok(Self(normalized))
+        } else {
+            Err(ContactError::InvalidPhoneNumber)
+        }
+    }
+
+    fn normalize(phone_number: &str) -> String {
+        let trimmed = phone_number.trim();
+        let trimmed = trimmed.strip_prefix('+').unwrap_or(trimmed);
+
+        trimmed
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '(' | ')'))
+            .collect()
+    }
+
+    fn is_valid(phone_number: &str) -> bool {
+        (7..=15).contains(&phone_number.len()) && phone_number.chars().all(|c| c.is_ascii_digit())
     }
 
     fn get_phone_number(&self) -> &str {
@@ -71,7 +142,7 @@ impl ContactPhoneNumber {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone, Default)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Default, Serialize, Deserialize)]
 struct SocialProfileLink(String);
 
 impl SocialProfileLink {
@@ -84,7 +155,7 @@ impl SocialProfileLink {
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SocialMediaWebsite {
     Github,
     Twitter,
@@ -93,7 +164,7 @@ pub enum SocialMediaWebsite {
     Unknown,
 }
 
-#[derive(PartialEq, Debug, Clone, Default)]
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
 struct SocialProfileList(HashMap<SocialProfileLink, SocialMediaWebsite>);
 
 impl SocialProfileList {
@@ -122,7 +193,7 @@ impl SocialProfileList {
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Default)]
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Contact {
     name: ContactName,
     address: Option<ContactAddress>,
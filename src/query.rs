@@ -0,0 +1,409 @@
+/* Workflow for query:
+ *   1. A ContactBook can be searched with a small text query language instead
+ *      of only listing every contact.
+ *   2. Queries support field filters (name:, email:, address:), presence
+ *      filters (has:phone, has:email, has:social), a social-source filter
+ *      (social:github), and an is:favorite filter.
+ *   3. Filters combine with implicit AND (juxtaposition), an explicit `or`
+ *      keyword, negation via a leading `-` or `not`, and parentheses for
+ *      grouping.
+ *   4. A bare word with no field matches a case-insensitive substring of the
+ *      contact's name, and an empty query matches every contact.
+ */
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::contact::{Contact, SocialMediaWebsite};
+
+#[derive(Debug, PartialEq)]
+pub enum QueryError {
+    UnknownField(String),
+    UnknownSocialSite(String),
+    UnknownPresenceFilter(String),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+}
+
+impl Error for QueryError {}
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownField(field) => write!(f, "Unknown query field `{field}`."),
+            Self::UnknownSocialSite(site) => write!(f, "Unknown social media site `{site}`."),
+            Self::UnknownPresenceFilter(what) => write!(f, "Unknown `has:` filter `{what}`."),
+            Self::UnexpectedToken(token) => write!(f, "Unexpected token `{token}`."),
+            Self::UnexpectedEnd => write!(f, "Unexpected end of query."),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    LParen,
+    RParen,
+    Minus,
+    Word(String),
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Minus);
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+
+                    if c == '"' {
+                        chars.next();
+                        for c in chars.by_ref() {
+                            if c == '"' {
+                                break;
+                            }
+                            word.push(c);
+                        }
+                    } else {
+                        word.push(c);
+                        chars.next();
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    tokens
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Field {
+    Name,
+    Email,
+    Address,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Predicate {
+    NameContains(String),
+    Field(Field, String),
+    HasPhone,
+    HasEmail,
+    HasSocial,
+    Social(SocialMediaWebsite),
+    IsFavorite,
+    Not(Box<Predicate>),
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+}
+
+impl Predicate {
+    pub(crate) fn eval(&self, contact: &Contact, is_favorite: bool) -> bool {
+        match self {
+            Self::NameContains(needle) => contains_case_insensitive(contact.get_name(), needle),
+            Self::Field(Field::Name, value) => {
+                contains_case_insensitive(contact.get_name(), value)
+            }
+            Self::Field(Field::Email, value) => contact
+                .get_email()
+                .is_some_and(|email| contains_case_insensitive(email, value)),
+            Self::Field(Field::Address, value) => contact
+                .get_address()
+                .is_some_and(|address| contains_case_insensitive(address, value)),
+            Self::HasPhone => contact.get_phone_number().is_some(),
+            Self::HasEmail => contact.get_email().is_some(),
+            Self::HasSocial => !contact.get_social_media_profiles().is_empty(),
+            Self::Social(site) => contact
+                .get_social_media_profiles()
+                .iter()
+                .any(|(profile_site, _)| profile_site == site),
+            Self::IsFavorite => is_favorite,
+            Self::Not(predicate) => !predicate.eval(contact, is_favorite),
+            Self::And(predicates) => predicates.iter().all(|p| p.eval(contact, is_favorite)),
+            Self::Or(predicates) => predicates.iter().any(|p| p.eval(contact, is_favorite)),
+        }
+    }
+}
+
+fn contains_case_insensitive(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn parse_social_site(value: &str) -> Result<SocialMediaWebsite, QueryError> {
+    match value.to_lowercase().as_str() {
+        "github" => Ok(SocialMediaWebsite::Github),
+        "twitter" => Ok(SocialMediaWebsite::Twitter),
+        "myspace" => Ok(SocialMediaWebsite::MySpace),
+        "linkedin" => Ok(SocialMediaWebsite::LinkedIn),
+        "unknown" => Ok(SocialMediaWebsite::Unknown),
+        _ => Err(QueryError::UnknownSocialSite(value.to_owned())),
+    }
+}
+
+fn parse_filter(word: &str) -> Result<Predicate, QueryError> {
+    match word.split_once(':') {
+        Some((field, value)) => match field.to_lowercase().as_str() {
+            "name" => Ok(Predicate::Field(Field::Name, value.to_owned())),
+            "email" => Ok(Predicate::Field(Field::Email, value.to_owned())),
+            "address" => Ok(Predicate::Field(Field::Address, value.to_owned())),
+            "has" => match value.to_lowercase().as_str() {
+                "phone" => Ok(Predicate::HasPhone),
+                "email" => Ok(Predicate::HasEmail),
+                "social" => Ok(Predicate::HasSocial),
+                _ => Err(QueryError::UnknownPresenceFilter(value.to_owned())),
+            },
+            "social" => parse_social_site(value).map(Predicate::Social),
+            "is" => match value.to_lowercase().as_str() {
+                "favorite" => Ok(Predicate::IsFavorite),
+                _ => Err(QueryError::UnknownField(format!("is:{value}"))),
+            },
+            _ => Err(QueryError::UnknownField(field.to_owned())),
+        },
+        None => Ok(Predicate::NameContains(word.to_owned())),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, position: 0 }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn can_start_factor(&self) -> bool {
+        match self.peek() {
+            Some(Token::LParen) | Some(Token::Minus) => true,
+            Some(Token::Word(word)) => !word.eq_ignore_ascii_case("or"),
+            Some(Token::RParen) | None => false,
+        }
+    }
+
+    // expr := term ("or" term)*
+    fn parse_expr(&mut self) -> Result<Predicate, QueryError> {
+        let mut terms = vec![self.parse_term()?];
+
+        while self.peek_is_keyword("or") {
+            self.advance();
+            terms.push(self.parse_term()?);
+        }
+
+        Ok(if terms.len() == 1 {
+            terms.pop().expect("terms has exactly one element")
+        } else {
+            Predicate::Or(terms)
+        })
+    }
+
+    // term := factor+
+    fn parse_term(&mut self) -> Result<Predicate, QueryError> {
+        let mut factors = Vec::new();
+
+        while self.can_start_factor() {
+            factors.push(self.parse_factor()?);
+        }
+
+        if factors.is_empty() {
+            return match self.advance() {
+                Some(token) => Err(QueryError::UnexpectedToken(format!("{token:?}"))),
+                None => Err(QueryError::UnexpectedEnd),
+            };
+        }
+
+        Ok(if factors.len() == 1 {
+            factors.pop().expect("factors has exactly one element")
+        } else {
+            Predicate::And(factors)
+        })
+    }
+
+    // factor := ("-" | "not") atom | atom
+    fn parse_factor(&mut self) -> Result<Predicate, QueryError> {
+        if matches!(self.peek(), Some(Token::Minus)) || self.peek_is_keyword("not") {
+            self.advance();
+            return Ok(Predicate::Not(Box::new(self.parse_atom()?)));
+        }
+
+        self.parse_atom()
+    }
+
+    // atom := "(" expr ")" | filter
+    fn parse_atom(&mut self) -> Result<Predicate, QueryError> {
+        match self.advance() {
+            Some(Token::LParen) => {
+                let expr = self.parse_expr()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(expr),
+                    Some(token) => Err(QueryError::UnexpectedToken(format!("{token:?}"))),
+                    None => Err(QueryError::UnexpectedEnd),
+                }
+            }
+            Some(Token::Word(word)) => parse_filter(&word),
+            Some(token) => Err(QueryError::UnexpectedToken(format!("{token:?}"))),
+            None => Err(QueryError::UnexpectedEnd),
+        }
+    }
+}
+
+pub(crate) fn parse(input: &str) -> Result<Predicate, QueryError> {
+    let tokens = tokenize(input);
+
+    if tokens.is_empty() {
+        return Ok(Predicate::And(Vec::new()));
+    }
+
+    let mut parser = Parser::new(tokens);
+    let predicate = parser.parse_expr()?;
+
+    match parser.advance() {
+        Some(token) => Err(QueryError::UnexpectedToken(format!("{token:?}"))),
+        None => Ok(predicate),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact() -> Contact {
+        Contact::new("Foo Bar")
+            .unwrap()
+            .add_email("foo@example.test")
+            .unwrap()
+            .add_phone_number("1234567890")
+            .unwrap()
+            .add_social_profile(SocialMediaWebsite::Github, "https://github.com/example")
+            .unwrap()
+    }
+
+    #[test]
+    fn an_empty_query_matches_every_contact() {
+        let predicate = parse("").unwrap();
+
+        assert!(predicate.eval(&contact(), false));
+    }
+
+    #[test]
+    fn a_bare_word_matches_the_contact_name_case_insensitively() {
+        let predicate = parse("foo").unwrap();
+
+        assert!(predicate.eval(&contact(), false));
+
+        let predicate = parse("baz").unwrap();
+
+        assert!(!predicate.eval(&contact(), false));
+    }
+
+    #[test]
+    fn field_filters_match_against_the_named_field() {
+        let predicate = parse("email:example.test").unwrap();
+
+        assert!(predicate.eval(&contact(), false));
+
+        let predicate = parse(r#"address:"Main St""#).unwrap();
+
+        assert!(!predicate.eval(&contact(), false));
+    }
+
+    #[test]
+    fn has_filters_check_field_presence() {
+        assert!(parse("has:phone").unwrap().eval(&contact(), false));
+        assert!(!parse("has:social").unwrap().eval(
+            &Contact::new("Foo Bar").unwrap(),
+            false
+        ));
+    }
+
+    #[test]
+    fn social_filters_match_on_the_social_source() {
+        let predicate = parse("social:github").unwrap();
+
+        assert!(predicate.eval(&contact(), false));
+
+        let predicate = parse("social:twitter").unwrap();
+
+        assert!(!predicate.eval(&contact(), false));
+    }
+
+    #[test]
+    fn is_favorite_uses_the_favorite_flag_passed_in() {
+        let predicate = parse("is:favorite").unwrap();
+
+        assert!(predicate.eval(&contact(), true));
+        assert!(!predicate.eval(&contact(), false));
+    }
+
+    #[test]
+    fn juxtaposition_is_an_implicit_and() {
+        let predicate = parse("foo has:phone").unwrap();
+
+        assert!(predicate.eval(&contact(), false));
+
+        let predicate = parse("foo has:social").unwrap();
+
+        assert!(!predicate.eval(&Contact::new("Foo Bar").unwrap(), false));
+    }
+
+    #[test]
+    fn or_combines_terms() {
+        let predicate = parse("baz or foo").unwrap();
+
+        assert!(predicate.eval(&contact(), false));
+    }
+
+    #[test]
+    fn a_leading_dash_or_not_negates_the_following_atom() {
+        assert!(!parse("-foo").unwrap().eval(&contact(), false));
+        assert!(!parse("not foo").unwrap().eval(&contact(), false));
+        assert!(parse("-baz").unwrap().eval(&contact(), false));
+    }
+
+    #[test]
+    fn parentheses_group_an_expression() {
+        let predicate = parse("-(foo or baz)").unwrap();
+
+        assert!(!predicate.eval(&contact(), false));
+    }
+
+    #[test]
+    fn an_unrecognized_field_name_is_a_query_error() {
+        assert_eq!(
+            parse("nickname:foo"),
+            Err(QueryError::UnknownField("nickname".to_owned()))
+        );
+    }
+}
@@ -93,3 +93,32 @@ fn contact() -> Result<(), ContactError> {
 
     Ok(())
 }
+
+#[test]
+fn adding_an_invalid_email_returns_an_error() {
+    let contact = Contact::new("Foo Bar").unwrap();
+
+    assert_eq!(
+        contact.add_email("not-an-email"),
+        Err(ContactError::InvalidEmail)
+    );
+}
+
+#[test]
+fn adding_a_phone_number_normalizes_common_formatting() {
+    let contact = Contact::new("Foo Bar").unwrap();
+
+    let contact = contact.add_phone_number("+1 (234) 567-8900").unwrap();
+
+    assert_eq!(contact.get_phone_number(), Some("12345678900"));
+}
+
+#[test]
+fn adding_an_invalid_phone_number_returns_an_error() {
+    let contact = Contact::new("Foo Bar").unwrap();
+
+    assert_eq!(
+        contact.add_phone_number("not a phone number"),
+        Err(ContactError::InvalidPhoneNumber)
+    );
+}